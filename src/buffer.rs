@@ -0,0 +1,356 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use futures::{Async, AsyncSink, Future, Poll, Sink, Stream};
+use futures::future::{ExecuteError, Executor};
+use futures::sync::{mpsc, oneshot};
+
+use Service;
+
+type Message<Req, Res, Err> = (Req, oneshot::Sender<Result<Res, Err>>);
+
+/// Error returned when a `Buffer`'s worker task has terminated, so the
+/// request could not be enqueued or its response could not be delivered.
+#[derive(Debug)]
+pub struct Closed(());
+
+impl fmt::Display for Closed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Buffer worker has terminated")
+    }
+}
+
+impl StdError for Closed {
+    fn description(&self) -> &str {
+        "Buffer worker has terminated"
+    }
+}
+
+/// A `Service` middleware giving bounded queueing and `Clone`/`Send`
+/// sharing to a service that is neither.
+///
+/// `Buffer::new` spawns the inner service onto an executor behind a
+/// bounded channel. Each `Buffer` handle is cheap to `Clone` and may be
+/// used concurrently from many tasks; `call` enqueues the request and a
+/// `poll_ready` returns `NotReady` once the channel is full, propagating
+/// backpressure to callers.
+pub struct Buffer<Req, Res, Err> {
+    tx: Arc<Mutex<mpsc::Sender<Message<Req, Res, Err>>>>,
+}
+
+impl<Req, Res, Err> Buffer<Req, Res, Err> {
+    /// Spawn `service` onto `executor`, fronted by a channel with room
+    /// for `capacity` outstanding requests, and return a handle to it.
+    pub fn new<S, E>(service: S, capacity: usize, executor: &E) -> Result<Self, ExecuteError<Worker<S>>>
+        where S: Service<Request = Req, Response = Res, Error = Err> + Send + 'static,
+              S::Future: Send + 'static,
+              Req: Send + 'static,
+              Res: Send + 'static,
+              Err: Send + 'static,
+              E: Executor<Worker<S>>,
+    {
+        let (tx, rx) = mpsc::channel(capacity);
+        let worker = Worker {
+            service: service,
+            rx: rx,
+            state: WorkerState::Receiving,
+        };
+
+        executor.execute(worker)?;
+
+        Ok(Buffer { tx: Arc::new(Mutex::new(tx)) })
+    }
+}
+
+impl<Req, Res, Err> Clone for Buffer<Req, Res, Err> {
+    fn clone(&self) -> Self {
+        Buffer { tx: self.tx.clone() }
+    }
+}
+
+impl<Req, Res, Err> Service for Buffer<Req, Res, Err>
+    where Err: From<Closed>,
+{
+    type Request = Req;
+    type Response = Res;
+    type Error = Err;
+    type Future = ResponseFuture<Req, Res, Err>;
+
+    fn poll_ready(&self) -> Poll<(), Self::Error> {
+        let mut tx = self.tx.lock().expect("Buffer sender lock poisoned");
+        tx.poll_ready().map_err(|_| Err::from(Closed(())))
+    }
+
+    fn call(&self, req: Req) -> Self::Future {
+        let (tx, rx) = oneshot::channel();
+
+        ResponseFuture {
+            state: ResponseState::Sending(Some((req, tx)), rx, self.tx.clone()),
+        }
+    }
+}
+
+enum ResponseState<Req, Res, Err> {
+    // Holds the message and the receive half of its reply channel until
+    // the message is accepted onto the worker's channel. Using
+    // `Sink::start_send` rather than `try_send` means a caller that raced
+    // another `call` between its `poll_ready` and this `call` (and so hit
+    // a channel that had just filled up) parks on the shared sender and is
+    // woken once a slot frees, instead of that transient race being
+    // mistaken for the worker having terminated.
+    Sending(Option<Message<Req, Res, Err>>, oneshot::Receiver<Result<Res, Err>>, Arc<Mutex<mpsc::Sender<Message<Req, Res, Err>>>>),
+    Waiting(oneshot::Receiver<Result<Res, Err>>),
+    // Transient placeholder used while moving the receiver out of `Waiting`
+    // during a poll; never observed across a `poll` call boundary.
+    Sent,
+}
+
+/// The future returned by `Buffer::call`.
+///
+/// It first waits for the request to be accepted onto the worker's
+/// channel, then waits on the receive half of the oneshot channel used to
+/// deliver the worker's response.
+pub struct ResponseFuture<Req, Res, Err> {
+    state: ResponseState<Req, Res, Err>,
+}
+
+impl<Req, Res, Err> Future for ResponseFuture<Req, Res, Err>
+    where Err: From<Closed>,
+{
+    type Item = Res;
+    type Error = Err;
+
+    fn poll(&mut self) -> Poll<Res, Err> {
+        if let ResponseState::Sending(ref mut msg, _, ref tx) = self.state {
+            let mut sender = tx.lock().expect("Buffer sender lock poisoned");
+            let message = msg.take().expect("ResponseFuture polled after completion");
+
+            match sender.start_send(message) {
+                Ok(AsyncSink::Ready) => {}
+                Ok(AsyncSink::NotReady(message)) => {
+                    *msg = Some(message);
+                    return Ok(Async::NotReady);
+                }
+                Err(_disconnected) => return Err(Err::from(Closed(()))),
+            }
+        }
+
+        let mut rx = match mem::replace(&mut self.state, ResponseState::Sent) {
+            ResponseState::Sending(_, rx, _) | ResponseState::Waiting(rx) => rx,
+            ResponseState::Sent => unreachable!("ResponseFuture polled after completion"),
+        };
+
+        match rx.poll() {
+            Ok(Async::Ready(Ok(res))) => Ok(Async::Ready(res)),
+            Ok(Async::Ready(Err(e))) => Err(e),
+            Ok(Async::NotReady) => {
+                self.state = ResponseState::Waiting(rx);
+                Ok(Async::NotReady)
+            }
+            Err(_canceled) => Err(Err::from(Closed(()))),
+        }
+    }
+}
+
+enum WorkerState<S: Service> {
+    Receiving,
+    Calling(S::Future, oneshot::Sender<Result<S::Response, S::Error>>),
+}
+
+/// The background task that drives a `Buffer`'s inner service, spawned
+/// by `Buffer::new`.
+pub struct Worker<S: Service> {
+    service: S,
+    rx: mpsc::Receiver<Message<S::Request, S::Response, S::Error>>,
+    state: WorkerState<S>,
+}
+
+impl<S: Service> Future for Worker<S> {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        loop {
+            if let WorkerState::Calling(ref mut future, _) = self.state {
+                let result = match future.poll() {
+                    Ok(Async::Ready(res)) => Ok(res),
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(e) => Err(e),
+                };
+
+                match mem::replace(&mut self.state, WorkerState::Receiving) {
+                    WorkerState::Calling(_, tx) => { let _ = tx.send(result); }
+                    WorkerState::Receiving => unreachable!(),
+                }
+
+                continue;
+            }
+
+            match try_ready!(self.rx.poll().map_err(|_| ())) {
+                Some((req, tx)) => {
+                    let future = self.service.call(req);
+                    self.state = WorkerState::Calling(future, tx);
+                }
+                None => return Ok(Async::Ready(())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc as std_mpsc;
+    use std::thread;
+
+    use futures::{Async, Future, Poll, future};
+    use futures::future::{ExecuteError, Executor};
+
+    use Service;
+    use super::{Buffer, Closed};
+
+    #[derive(Debug, PartialEq)]
+    struct Error;
+
+    impl From<Closed> for Error {
+        fn from(_: Closed) -> Error {
+            Error
+        }
+    }
+
+    struct AddOne;
+
+    impl Service for AddOne {
+        type Request = u32;
+        type Response = u32;
+        type Error = Error;
+        type Future = future::FutureResult<u32, Error>;
+
+        fn poll_ready(&self) -> Poll<(), Error> {
+            Ok(Async::Ready(()))
+        }
+
+        fn call(&self, req: u32) -> Self::Future {
+            future::ok(req + 1)
+        }
+    }
+
+    struct ThreadPool;
+
+    impl<F> Executor<F> for ThreadPool
+        where F: Future<Item = (), Error = ()> + Send + 'static,
+    {
+        fn execute(&self, future: F) -> Result<(), ExecuteError<F>> {
+            thread::spawn(move || { let _ = future.wait(); });
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn round_trips_a_request_through_the_worker() {
+        let buffer = Buffer::new(AddOne, 1, &ThreadPool).unwrap();
+
+        assert_eq!(buffer.call(41).wait(), Ok(42));
+    }
+
+    #[test]
+    fn clones_share_the_same_worker() {
+        let buffer = Buffer::new(AddOne, 1, &ThreadPool).unwrap();
+        let other = buffer.clone();
+
+        assert_eq!(buffer.call(1).wait(), Ok(2));
+        assert_eq!(other.call(2).wait(), Ok(3));
+    }
+
+    /// A service whose future never resolves, signalling over
+    /// `started` the first time it is polled. Used to pin the worker in
+    /// `WorkerState::Calling` forever, so it stops draining the channel
+    /// and the channel's capacity can be observed filling up.
+    struct Stuck {
+        started: std_mpsc::SyncSender<()>,
+    }
+
+    impl Service for Stuck {
+        type Request = u32;
+        type Response = u32;
+        type Error = Error;
+        type Future = StuckFuture;
+
+        fn poll_ready(&self) -> Poll<(), Error> {
+            Ok(Async::Ready(()))
+        }
+
+        fn call(&self, _req: u32) -> Self::Future {
+            StuckFuture(self.started.clone())
+        }
+    }
+
+    struct StuckFuture(std_mpsc::SyncSender<()>);
+
+    impl Future for StuckFuture {
+        type Item = u32;
+        type Error = Error;
+
+        fn poll(&mut self) -> Poll<u32, Error> {
+            let _ = self.0.send(());
+            Ok(Async::NotReady)
+        }
+    }
+
+    #[test]
+    fn poll_ready_reports_not_ready_once_the_channel_is_full() {
+        let (started_tx, started_rx) = std_mpsc::sync_channel(0);
+        let buffer = Buffer::new(Stuck { started: started_tx }, 1, &ThreadPool).unwrap();
+
+        future::lazy(|| {
+            // Accept the first request onto the channel; the worker
+            // thread dequeues it and calls into `Stuck`, which never
+            // resolves, so the worker never polls the channel again.
+            let mut first = buffer.call(1);
+            assert_eq!(first.poll(), Ok(Async::NotReady));
+
+            future::ok::<(), ()>(())
+        }).wait().unwrap();
+
+        // Block until the worker has actually dequeued the first
+        // request, freeing it from the channel before we fill it.
+        started_rx.recv().unwrap();
+
+        future::lazy(|| {
+            // A bounded channel of capacity 1 admits one more send
+            // beyond its configured buffer, since its single `Sender`
+            // carries a guaranteed slot of its own; both are consumed
+            // by these two sends, with nothing left to drain them.
+            let mut second = buffer.call(2);
+            assert_eq!(second.poll(), Ok(Async::NotReady));
+            let mut third = buffer.call(3);
+            assert_eq!(third.poll(), Ok(Async::NotReady));
+
+            assert_eq!(buffer.poll_ready(), Ok(Async::NotReady));
+
+            future::ok::<(), ()>(())
+        }).wait().unwrap();
+    }
+
+    struct Dropping;
+
+    impl<F> Executor<F> for Dropping
+        where F: Future<Item = (), Error = ()> + Send + 'static,
+    {
+        fn execute(&self, future: F) -> Result<(), ExecuteError<F>> {
+            // Simulates a worker that is gone before it ever runs, by
+            // dropping its future (and the channel receiver it holds)
+            // outright instead of spawning it.
+            drop(future);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn surfaces_closed_once_the_worker_is_gone() {
+        let buffer = Buffer::new(AddOne, 1, &Dropping).unwrap();
+
+        assert_eq!(buffer.call(1).wait(), Err(Error));
+    }
+}
@@ -0,0 +1,198 @@
+use std::rc::Rc;
+
+use futures::{Future, Poll};
+
+use {Middleware, Service};
+
+/// An asynchronous predicate checked against a request before it is
+/// allowed to reach a service.
+///
+/// This is the check half of `Filter`: `Ok(())` admits the request,
+/// `Err(Self::Error)` rejects it without ever calling the wrapped
+/// service.
+pub trait Predicate<Request> {
+    /// The error produced when a request fails the check.
+    type Error;
+
+    /// The future returned by `check`.
+    type Future: Future<Item = (), Error = Self::Error>;
+
+    /// Check `req`, resolving to `Ok(())` if it should be admitted.
+    fn check(&self, req: &Request) -> Self::Future;
+}
+
+/// Middleware that runs a `Predicate` against each request before it
+/// reaches the wrapped service, rejecting requests that fail the check.
+///
+/// The wrapped service's `Error` type must implement `From<P::Error>` so
+/// a predicate failure can be surfaced through the normal error path.
+pub struct Filter<P> {
+    predicate: P,
+}
+
+impl<P> Filter<P> {
+    /// Create a new `Filter` middleware from a predicate.
+    pub fn new(predicate: P) -> Self {
+        Filter { predicate: predicate }
+    }
+}
+
+impl<P, S> Middleware<S> for Filter<P>
+    where S: Service,
+          P: Predicate<S::Request>,
+          S::Error: From<P::Error>,
+{
+    type WrappedService = FilterService<P, S>;
+
+    fn wrap(self, service: S) -> Self::WrappedService {
+        FilterService {
+            predicate: self.predicate,
+            service: Rc::new(service),
+        }
+    }
+}
+
+/// A `Service` that checks each request against a `Predicate` before
+/// forwarding it to the inner service. Produced by `Filter::wrap`.
+pub struct FilterService<P, S> {
+    predicate: P,
+    service: Rc<S>,
+}
+
+impl<P, S> Service for FilterService<P, S>
+    where S: Service,
+          P: Predicate<S::Request>,
+          S::Error: From<P::Error>,
+{
+    type Request = S::Request;
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = FilterFuture<P, S>;
+
+    fn poll_ready(&self) -> Poll<(), Self::Error> {
+        self.service.poll_ready()
+    }
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        FilterFuture {
+            state: FilterState::Checking(self.predicate.check(&req), Some(req)),
+            service: self.service.clone(),
+        }
+    }
+}
+
+enum FilterState<P, Req, F> {
+    Checking(P, Option<Req>),
+    Calling(F),
+}
+
+/// The future returned by `FilterService::call`.
+///
+/// It first drives the predicate future to completion, holding the
+/// request, then transitions to driving the inner service's call future.
+pub struct FilterFuture<P, S>
+    where S: Service,
+          P: Predicate<S::Request>,
+{
+    state: FilterState<P::Future, S::Request, S::Future>,
+    service: Rc<S>,
+}
+
+impl<P, S> Future for FilterFuture<P, S>
+    where S: Service,
+          P: Predicate<S::Request>,
+          S::Error: From<P::Error>,
+{
+    type Item = S::Response;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            let call = match self.state {
+                FilterState::Checking(ref mut check, ref mut req) => {
+                    try_ready!(check.poll().map_err(S::Error::from));
+                    let req = req.take().expect("FilterFuture polled after completion");
+                    self.service.call(req)
+                }
+                FilterState::Calling(ref mut call) => return call.poll(),
+            };
+            self.state = FilterState::Calling(call);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use futures::{Async, Future, Poll, future};
+
+    use Service;
+    use super::{Filter, Predicate};
+
+    struct EvenOnly;
+
+    #[derive(Debug, PartialEq)]
+    struct Rejected;
+
+    impl Predicate<u32> for EvenOnly {
+        type Error = Rejected;
+        type Future = future::FutureResult<(), Rejected>;
+
+        fn check(&self, req: &u32) -> Self::Future {
+            if req % 2 == 0 {
+                future::ok(())
+            } else {
+                future::err(Rejected)
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Error;
+
+    impl From<Rejected> for Error {
+        fn from(_: Rejected) -> Error {
+            Error
+        }
+    }
+
+    struct CountingService {
+        calls: Rc<Cell<u32>>,
+    }
+
+    impl Service for CountingService {
+        type Request = u32;
+        type Response = u32;
+        type Error = Error;
+        type Future = future::FutureResult<u32, Error>;
+
+        fn poll_ready(&self) -> Poll<(), Error> {
+            Ok(Async::Ready(()))
+        }
+
+        fn call(&self, req: u32) -> Self::Future {
+            self.calls.set(self.calls.get() + 1);
+            future::ok(req)
+        }
+    }
+
+    #[test]
+    fn admits_a_request_that_passes_the_predicate() {
+        let calls = Rc::new(Cell::new(0));
+        let svc = CountingService { calls: calls.clone() }.wrap(Filter::new(EvenOnly));
+
+        assert_eq!(svc.call(2).wait(), Ok(2));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn rejects_a_request_without_ever_calling_the_inner_service() {
+        let calls = Rc::new(Cell::new(0));
+        let svc = CountingService { calls: calls.clone() }.wrap(Filter::new(EvenOnly));
+
+        assert_eq!(svc.call(3).wait(), Err(Error));
+        assert_eq!(calls.get(), 0);
+    }
+}
@@ -0,0 +1,176 @@
+use std::io;
+
+use {Middleware, NewService, Service};
+
+/// Decorates a `Service`, producing a new `Service`.
+///
+/// Unlike `Middleware`, a `Layer` is not parameterized by the `Service` it
+/// wraps: there is no `S: Service` bound on `S`. This makes it possible to
+/// build and compose middleware stacks before the concrete service type is
+/// known, for example as a `Vec<Box<Layer<_, Service = _>>>`.
+pub trait Layer<S> {
+    /// The wrapped service produced by this layer.
+    type Service;
+
+    /// Wrap `inner` with this layer, producing `Self::Service`.
+    fn layer(&self, inner: S) -> Self::Service;
+
+    /// Compose two layers together. The lefthand side is applied to the
+    /// service first, and the righthand side is applied to the service
+    /// produced by the lefthand side.
+    ///
+    /// This allows you to build layer stacks before knowing exactly which
+    /// service that stack applies to.
+    fn chain<L>(self, outer: L) -> LayerChain<Self, L>
+        where L: Layer<Self::Service>,
+              Self: Sized,
+    {
+        LayerChain {
+            inner: self,
+            outer: outer,
+        }
+    }
+}
+
+/// Two layers, chained together. This type is produced by the `chain`
+/// method on the `Layer` trait.
+pub struct LayerChain<Inner, Outer> {
+    inner: Inner,
+    outer: Outer,
+}
+
+impl<S, Inner, Outer> Layer<S> for LayerChain<Inner, Outer>
+    where Inner: Layer<S>,
+          Outer: Layer<Inner::Service>,
+{
+    type Service = Outer::Service;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        self.outer.layer(self.inner.layer(inner))
+    }
+}
+
+/// Bridges existing `Middleware` implementors so that they can be used
+/// wherever a `Layer` is expected.
+///
+/// Because `Layer::layer` takes `&self` while `Middleware::wrap` consumes
+/// `self`, the bridge requires the middleware to be `Clone`.
+impl<S, M> Layer<S> for M
+    where S: Service,
+          M: Middleware<S> + Clone,
+{
+    type Service = M::WrappedService;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        self.clone().wrap(inner)
+    }
+}
+
+/// A `NewService` that wraps every service produced by an inner factory
+/// with a `Layer`.
+///
+/// This type is produced by `NewService::wrap`.
+pub struct NewServiceWrapper<L, S> {
+    service: S,
+    layer: L,
+}
+
+impl<L, S> NewServiceWrapper<L, S> {
+    pub(crate) fn new(service: S, layer: L) -> Self {
+        NewServiceWrapper { service: service, layer: layer }
+    }
+}
+
+impl<L, S> NewService for NewServiceWrapper<L, S>
+    where S: NewService,
+          L: Layer<S::Instance>,
+          L::Service: Service,
+{
+    type Request = <L::Service as Service>::Request;
+    type Response = <L::Service as Service>::Response;
+    type Error = <L::Service as Service>::Error;
+    type Instance = L::Service;
+
+    fn new_service(&self) -> io::Result<Self::Instance> {
+        Ok(self.layer.layer(self.service.new_service()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{Async, Future, Poll, future};
+
+    use {Middleware, Service};
+    use super::Layer;
+
+    struct AddOne;
+
+    impl Service for AddOne {
+        type Request = u32;
+        type Response = u32;
+        type Error = ();
+        type Future = future::FutureResult<u32, ()>;
+
+        fn poll_ready(&self) -> Poll<(), ()> {
+            Ok(Async::Ready(()))
+        }
+
+        fn call(&self, req: u32) -> Self::Future {
+            future::ok(req + 1)
+        }
+    }
+
+    #[derive(Clone)]
+    struct AddN(u32);
+
+    impl<S> Middleware<S> for AddN
+        where S: Service<Request = u32, Response = u32>,
+              S::Future: 'static,
+    {
+        type WrappedService = AddNService<S>;
+
+        fn wrap(self, service: S) -> Self::WrappedService {
+            AddNService { service: service, n: self.0 }
+        }
+    }
+
+    struct AddNService<S> {
+        service: S,
+        n: u32,
+    }
+
+    impl<S> Service for AddNService<S>
+        where S: Service<Request = u32, Response = u32>,
+              S::Future: 'static,
+    {
+        type Request = u32;
+        type Response = u32;
+        type Error = S::Error;
+        type Future = Box<Future<Item = u32, Error = S::Error>>;
+
+        fn poll_ready(&self) -> Poll<(), S::Error> {
+            self.service.poll_ready()
+        }
+
+        fn call(&self, req: u32) -> Self::Future {
+            let n = self.n;
+            Box::new(self.service.call(req).map(move |res| res + n))
+        }
+    }
+
+    #[test]
+    fn chain_applies_the_inner_layer_then_the_outer_layer() {
+        let layer = Layer::<AddOne>::chain(AddN(10), AddN(100));
+
+        let svc = layer.layer(AddOne);
+        assert_eq!(svc.call(1).wait(), Ok(1 + 1 + 10 + 100));
+    }
+
+    #[test]
+    fn a_middleware_can_be_used_wherever_a_layer_is_expected() {
+        let layer = AddN(5);
+
+        let svc = layer.layer(AddOne);
+        assert_eq!(svc.call(1).wait(), Ok(1 + 1 + 5));
+    }
+}
@@ -8,18 +8,34 @@
 //#![deny(missing_docs)]
 #![doc(html_root_url = "https://docs.rs/tokio-service/0.1")]
 
+#[macro_use]
 extern crate futures;
+extern crate tokio_timer;
 
-use futures::Future;
+use futures::{Future, Poll};
 
 use std::io;
 use std::rc::Rc;
 use std::sync::Arc;
 
+mod buffer;
+mod filter;
+mod layer;
 mod middleware;
+mod next;
+mod rate_limit;
+mod service_ext;
 pub mod stream;
+mod timeout;
 
+pub use self::buffer::*;
+pub use self::filter::*;
+pub use self::layer::*;
 pub use self::middleware::*;
+pub use self::next::*;
+pub use self::rate_limit::*;
+pub use self::service_ext::*;
+pub use self::timeout::*;
 
 /// An asynchronous function from `Request` to a `Response`.
 ///
@@ -98,6 +114,15 @@ pub trait Service {
     /// The future response value.
     type Future: Future<Item = Self::Response, Error = Self::Error>;
 
+    /// Returns `Ready` when the service is able to process one request.
+    ///
+    /// If the service is at capacity, returns `NotReady` and arranges for
+    /// the current task to be notified when the service becomes ready
+    /// again. A successful readiness poll reserves exactly one slot,
+    /// which is consumed by the very next call to `call`. Callers must
+    /// always drive `poll_ready` to `Ready` before invoking `call`.
+    fn poll_ready(&self) -> Poll<(), Self::Error>;
+
     /// Process the request and return the response asynchronously.
     fn call(&self, req: Self::Request) -> Self::Future;
 
@@ -127,11 +152,13 @@ pub trait NewService {
     /// Create and return a new service value.
     fn new_service(&self) -> io::Result<Self::Instance>;
 
-    fn wrap<M>(self, new_middleware: M) -> NewServiceWrapper<M, Self>
-        where M: NewMiddleware<Self::Instance>,
+    /// Wrap this factory so that every service it produces is decorated
+    /// with `layer`.
+    fn wrap<L>(self, layer: L) -> NewServiceWrapper<L, Self>
+        where L: Layer<Self::Instance>,
               Self: Sized,
     {
-        new_middleware.wrap(self)
+        NewServiceWrapper::new(self, layer)
     }
 }
 
@@ -177,6 +204,10 @@ impl<S: Service + ?Sized> Service for Box<S> {
     type Error = S::Error;
     type Future = S::Future;
 
+    fn poll_ready(&self) -> Poll<(), S::Error> {
+        (**self).poll_ready()
+    }
+
     fn call(&self, request: S::Request) -> S::Future {
         (**self).call(request)
     }
@@ -188,6 +219,10 @@ impl<S: Service + ?Sized> Service for Rc<S> {
     type Error = S::Error;
     type Future = S::Future;
 
+    fn poll_ready(&self) -> Poll<(), S::Error> {
+        (**self).poll_ready()
+    }
+
     fn call(&self, request: S::Request) -> S::Future {
         (**self).call(request)
     }
@@ -199,6 +234,10 @@ impl<S: Service + ?Sized> Service for Arc<S> {
     type Error = S::Error;
     type Future = S::Future;
 
+    fn poll_ready(&self) -> Poll<(), S::Error> {
+        (**self).poll_ready()
+    }
+
     fn call(&self, request: S::Request) -> S::Future {
         (**self).call(request)
     }
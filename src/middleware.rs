@@ -61,6 +61,12 @@ use Service;
 ///     type Error = S::Error;
 ///     type Future = Box<Future<Item = Self::Response, Error = Self::Error>>;
 ///
+///     fn poll_ready(&self) -> Poll<(), Self::Error> {
+///         // A timeout imposes no extra capacity limit of its own, so it
+///         // simply delegates readiness to the service it wraps.
+///         self.upstream.poll_ready()
+///     }
+///
 ///     fn call(&self, req: Self::Req) -> Self::Future {
 ///         let timeout = self.timeout.timeout()
 ///             .and_then(|timeout| Err(Self::Error::from(timeout)));
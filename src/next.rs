@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use futures::{Future, Poll};
+
+use Service;
+
+/// The remaining middleware to run for a request, followed by the
+/// terminal service.
+///
+/// A `Handler` receives a `Next` and may call `next.run(req)` to continue
+/// the chain, or return its own response future without calling `next` at
+/// all, short-circuiting the rest of the chain (and the wrapped service).
+pub struct Next<'a, Req, Res, Err>
+    where Req: 'a, Res: 'a, Err: 'a,
+{
+    middleware: &'a [Arc<Handler<Request = Req, Response = Res, Error = Err> + Send + Sync>],
+    service: &'a (Fn(Req) -> Box<Future<Item = Res, Error = Err> + Send> + Send + Sync),
+}
+
+impl<'a, Req, Res, Err> Next<'a, Req, Res, Err> {
+    /// Run the next handler in the chain, or the wrapped service if none
+    /// remain.
+    pub fn run(self, req: Req) -> Box<Future<Item = Res, Error = Err> + Send> {
+        match self.middleware.split_first() {
+            Some((handler, rest)) => {
+                let next = Next {
+                    middleware: rest,
+                    service: self.service,
+                };
+                handler.handle(req, next)
+            }
+            None => (self.service)(req),
+        }
+    }
+}
+
+/// Inspects a request and either produces a response directly or forwards
+/// it to `next`.
+///
+/// Unlike `Middleware`, which can only wrap a whole service, a `Handler`
+/// can short-circuit the chain: returning a response future without
+/// calling `next.run` means the rest of the chain, and the wrapped
+/// service, are never invoked. This is useful for things like auth
+/// rejection, cache hits, or request normalization.
+pub trait Handler: Send + Sync {
+    /// Requests handled by this handler.
+    type Request;
+
+    /// Responses given by this handler.
+    type Response;
+
+    /// Errors produced by this handler.
+    type Error;
+
+    /// Inspect `req`, then either respond directly or call
+    /// `next.run(req)` to continue the chain.
+    fn handle(&self, req: Self::Request, next: Next<Self::Request, Self::Response, Self::Error>)
+        -> Box<Future<Item = Self::Response, Error = Self::Error> + Send>;
+}
+
+/// Adapts a stack of `Handler`s and a terminal `Service` back into a
+/// single `Service`, so it plugs into the existing `NewService`
+/// machinery.
+///
+/// Handlers run in the reverse of the order passed to `new`: the last
+/// handler given is the outermost layer and sees the request first.
+pub struct HandlerService<S: Service> {
+    middleware: Vec<Arc<Handler<Request = S::Request, Response = S::Response, Error = S::Error> + Send + Sync>>,
+    service: S,
+}
+
+impl<S: Service> HandlerService<S> {
+    /// Create a new `HandlerService` wrapping `service` with `middleware`.
+    pub fn new(
+        mut middleware: Vec<Arc<Handler<Request = S::Request, Response = S::Response, Error = S::Error> + Send + Sync>>,
+        service: S,
+    ) -> Self {
+        middleware.reverse();
+        HandlerService {
+            middleware: middleware,
+            service: service,
+        }
+    }
+}
+
+impl<S> Service for HandlerService<S>
+    where S: Service + Sync,
+          S::Request: 'static,
+          S::Response: 'static,
+          S::Error: 'static,
+          S::Future: Send + 'static,
+{
+    type Request = S::Request;
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Box<Future<Item = Self::Response, Error = Self::Error> + Send>;
+
+    fn poll_ready(&self) -> Poll<(), Self::Error> {
+        self.service.poll_ready()
+    }
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        let service = &self.service;
+        let terminal = move |req| -> Box<Future<Item = S::Response, Error = S::Error> + Send> {
+            Box::new(service.call(req))
+        };
+
+        let next = Next {
+            middleware: &self.middleware,
+            service: &terminal,
+        };
+
+        next.run(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::{Future, future};
+
+    use Service;
+    use super::{Handler, HandlerService, Next};
+
+    struct Passthrough;
+
+    impl Handler for Passthrough {
+        type Request = u32;
+        type Response = u32;
+        type Error = ();
+
+        fn handle(&self, req: u32, next: Next<u32, u32, ()>)
+            -> Box<Future<Item = u32, Error = ()> + Send>
+        {
+            next.run(req)
+        }
+    }
+
+    struct ShortCircuit;
+
+    impl Handler for ShortCircuit {
+        type Request = u32;
+        type Response = u32;
+        type Error = ();
+
+        fn handle(&self, _req: u32, _next: Next<u32, u32, ()>)
+            -> Box<Future<Item = u32, Error = ()> + Send>
+        {
+            Box::new(future::ok(0))
+        }
+    }
+
+    struct AddOne;
+
+    impl Service for AddOne {
+        type Request = u32;
+        type Response = u32;
+        type Error = ();
+        type Future = future::FutureResult<u32, ()>;
+
+        fn poll_ready(&self) -> ::futures::Poll<(), ()> {
+            Ok(::futures::Async::Ready(()))
+        }
+
+        fn call(&self, req: u32) -> Self::Future {
+            future::ok(req + 1)
+        }
+    }
+
+    #[test]
+    fn runs_the_wrapped_service_when_no_handler_short_circuits() {
+        let handlers: Vec<Arc<Handler<Request = u32, Response = u32, Error = ()> + Send + Sync>> =
+            vec![Arc::new(Passthrough)];
+        let svc = HandlerService::new(handlers, AddOne);
+
+        assert_eq!(svc.call(41).wait(), Ok(42));
+    }
+
+    #[test]
+    fn a_handler_can_short_circuit_the_chain() {
+        let handlers: Vec<Arc<Handler<Request = u32, Response = u32, Error = ()> + Send + Sync>> =
+            vec![Arc::new(ShortCircuit), Arc::new(Passthrough)];
+        let svc = HandlerService::new(handlers, AddOne);
+
+        // Handlers run in reverse order, so `Passthrough` (last in the
+        // list) runs first and forwards to `ShortCircuit`, which returns
+        // without ever reaching `AddOne`.
+        assert_eq!(svc.call(41).wait(), Ok(0));
+    }
+}
@@ -0,0 +1,195 @@
+use std::marker::PhantomData;
+
+use futures::{Async, Future, IntoFuture, Poll};
+use futures::future;
+
+use Service;
+
+/// Extension methods for every `Service`, providing small combinator
+/// adapters so trivial response/error transformations don't require a
+/// full trait impl.
+///
+/// This plays the same role for `Service` that the combinators on
+/// `Future` itself (`map`, `map_err`, `and_then`) play for futures.
+pub trait ServiceExt: Service {
+    /// Map this service's successful response through `f`.
+    ///
+    /// The service's readiness and error handling are untouched; only
+    /// the value produced by a successful `call` is transformed.
+    fn map<F, R>(self, f: F) -> Map<Self, F>
+        where Self: Sized,
+              F: Fn(Self::Response) -> R + Clone,
+    {
+        Map { service: self, f: f }
+    }
+
+    /// Map this service's error through `f`.
+    fn map_err<F, E>(self, f: F) -> MapErr<Self, F>
+        where Self: Sized,
+              F: Fn(Self::Error) -> E + Clone,
+    {
+        MapErr { service: self, f: f }
+    }
+
+    /// Chain an asynchronous follow-up onto this service's successful
+    /// response.
+    ///
+    /// `f` is called with the response and its returned future is
+    /// driven to completion before the combined future resolves.
+    fn and_then<F, B>(self, f: F) -> AndThen<Self, F>
+        where Self: Sized,
+              F: Fn(Self::Response) -> B + Clone,
+              B: IntoFuture<Error = Self::Error>,
+    {
+        AndThen { service: self, f: f }
+    }
+}
+
+impl<S: Service> ServiceExt for S {}
+
+/// A `Service` that maps the response of an inner service through a
+/// function. Produced by `ServiceExt::map`.
+pub struct Map<S, F> {
+    service: S,
+    f: F,
+}
+
+impl<S, F, R> Service for Map<S, F>
+    where S: Service,
+          F: Fn(S::Response) -> R + Clone,
+{
+    type Request = S::Request;
+    type Response = R;
+    type Error = S::Error;
+    type Future = future::Map<S::Future, F>;
+
+    fn poll_ready(&self) -> Poll<(), Self::Error> {
+        self.service.poll_ready()
+    }
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        self.service.call(req).map(self.f.clone())
+    }
+}
+
+/// A `Service` that maps the error of an inner service through a
+/// function. Produced by `ServiceExt::map_err`.
+pub struct MapErr<S, F> {
+    service: S,
+    f: F,
+}
+
+impl<S, F, E> Service for MapErr<S, F>
+    where S: Service,
+          F: Fn(S::Error) -> E + Clone,
+{
+    type Request = S::Request;
+    type Response = S::Response;
+    type Error = E;
+    type Future = future::MapErr<S::Future, F>;
+
+    fn poll_ready(&self) -> Poll<(), Self::Error> {
+        self.service.poll_ready().map_err(self.f.clone())
+    }
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        self.service.call(req).map_err(self.f.clone())
+    }
+}
+
+/// A `Service` that chains an asynchronous follow-up onto an inner
+/// service's response. Produced by `ServiceExt::and_then`.
+pub struct AndThen<S, F> {
+    service: S,
+    f: F,
+}
+
+impl<S, F, B> Service for AndThen<S, F>
+    where S: Service,
+          F: Fn(S::Response) -> B + Clone,
+          B: IntoFuture<Error = S::Error>,
+{
+    type Request = S::Request;
+    type Response = B::Item;
+    type Error = S::Error;
+    type Future = future::AndThen<S::Future, B, F>;
+
+    fn poll_ready(&self) -> Poll<(), Self::Error> {
+        self.service.poll_ready()
+    }
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        self.service.call(req).and_then(self.f.clone())
+    }
+}
+
+/// Turn a closure into a `Service`.
+///
+/// This mirrors how the blanket `NewService` impl lets a
+/// `Fn() -> io::Result<R>` closure act directly as a service factory:
+/// here, a `Fn(Request) -> IntoFuture` closure acts directly as a
+/// `Service`, which is often all a one-off service or middleware leaf
+/// node needs.
+pub fn service_fn<F, Req, B>(f: F) -> ServiceFn<F, Req, B>
+    where F: Fn(Req) -> B,
+          B: IntoFuture,
+{
+    ServiceFn { f: f, _marker: PhantomData }
+}
+
+/// A `Service` implemented by a closure. Produced by `service_fn`.
+pub struct ServiceFn<F, Req, B> {
+    f: F,
+    _marker: PhantomData<fn(Req) -> B>,
+}
+
+impl<F, Req, B> Service for ServiceFn<F, Req, B>
+    where F: Fn(Req) -> B,
+          B: IntoFuture,
+{
+    type Request = Req;
+    type Response = B::Item;
+    type Error = B::Error;
+    type Future = B::Future;
+
+    fn poll_ready(&self) -> Poll<(), Self::Error> {
+        Ok(Async::Ready(()))
+    }
+
+    fn call(&self, req: Req) -> Self::Future {
+        (self.f)(req).into_future()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{future, Future};
+
+    use Service;
+    use super::{service_fn, ServiceExt};
+
+    #[test]
+    fn service_fn_calls_the_closure() {
+        let svc = service_fn(|req: u32| future::ok::<_, ()>(req + 1));
+        assert_eq!(svc.call(1).wait(), Ok(2));
+    }
+
+    #[test]
+    fn map_transforms_the_response() {
+        let svc = service_fn(|req: u32| future::ok::<_, ()>(req)).map(|res| res * 2);
+        assert_eq!(svc.call(3).wait(), Ok(6));
+    }
+
+    #[test]
+    fn map_err_transforms_the_error() {
+        let svc = service_fn(|_: u32| future::err::<u32, _>("boom")).map_err(|e| e.len());
+        assert_eq!(svc.call(0).wait(), Err(4));
+    }
+
+    #[test]
+    fn and_then_chains_a_follow_up() {
+        let svc = service_fn(|req: u32| future::ok::<_, ()>(req))
+            .and_then(|res| future::ok(res + 10));
+        assert_eq!(svc.call(5).wait(), Ok(15));
+    }
+}
@@ -8,7 +8,7 @@ use std::io;
 use std::rc::Rc;
 use std::sync::Arc;
 
-use futures::Stream;
+use futures::{Poll, Stream};
 
 pub trait StreamService {
     type Request;
@@ -16,6 +16,13 @@ pub trait StreamService {
     type Error;
     type Stream: Stream<Item = Self::Response, Error = Self::Error>;
 
+    /// Returns `Ready` when the service is able to process one request.
+    ///
+    /// See `Service::poll_ready` for the full contract: a successful
+    /// readiness poll reserves exactly one slot, consumed by the next
+    /// call to `call`.
+    fn poll_ready(&self) -> Poll<(), Self::Error>;
+
     fn call(&self, req: Self::Request) -> Self::Stream;
 
     fn wrap<M>(self, middleware: M) -> M::WrappedService
@@ -39,6 +46,10 @@ impl<S: StreamService + ?Sized> StreamService for Box<S> {
     type Error = S::Error;
     type Stream = S::Stream;
 
+    fn poll_ready(&self) -> Poll<(), S::Error> {
+        (**self).poll_ready()
+    }
+
     fn call(&self, request: S::Request) -> S::Stream {
         (**self).call(request)
     }
@@ -50,6 +61,10 @@ impl<S: StreamService + ?Sized> StreamService for Rc<S> {
     type Error = S::Error;
     type Stream = S::Stream;
 
+    fn poll_ready(&self) -> Poll<(), S::Error> {
+        (**self).poll_ready()
+    }
+
     fn call(&self, request: S::Request) -> S::Stream {
         (**self).call(request)
     }
@@ -61,6 +76,10 @@ impl<S: StreamService + ?Sized> StreamService for Arc<S> {
     type Error = S::Error;
     type Stream = S::Stream;
 
+    fn poll_ready(&self) -> Poll<(), S::Error> {
+        (**self).poll_ready()
+    }
+
     fn call(&self, request: S::Request) -> S::Stream {
         (**self).call(request)
     }
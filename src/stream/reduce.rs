@@ -1,7 +1,7 @@
 use std::io;
 use std::marker::PhantomData;
 
-use {Middleware, NewMiddleware, Service, NewService};
+use {Layer, Middleware, Service, NewService};
 use stream::{StreamService, NewStreamService};
 
 pub trait StreamReduce<S: StreamService> {
@@ -59,13 +59,20 @@ pub trait NewStreamReduce<S: StreamService> {
         }
     }
 
-    fn chain<M>(self, new_middleware: M) -> NewStreamReduceMiddlewareChain<S, Self, M>
-        where M: NewMiddleware<Self::ReducedService>,
+    /// Chain a `Layer` onto this reducer, applied to the `Service` it
+    /// produces.
+    ///
+    /// A clone of `layer` is stored in every `Instance` this factory
+    /// produces, and each `Instance` applies it to the service that its
+    /// reducer yields.
+    fn chain<L>(self, layer: L) -> NewStreamReduceLayerChain<S, Self, L>
+        where L: Layer<Self::ReducedService> + Clone,
+              L::Service: Service,
               Self: Sized,
     {
-        NewStreamReduceMiddlewareChain {
+        NewStreamReduceLayerChain {
             reducer: self,
-            middleware: new_middleware,
+            layer: layer,
             _marker: PhantomData,
         }
     }
@@ -91,27 +98,63 @@ impl<R, S, W> NewService for NewStreamServiceReducer<R, S>
     }
 }
 
-pub struct NewStreamReduceMiddlewareChain<S, R, M>
+/// A `NewStreamReduce` that applies a `Layer` to the service produced by
+/// an inner reducer. Produced by `NewStreamReduce::chain`.
+pub struct NewStreamReduceLayerChain<S, R, L>
 where
     S: StreamService,
     R: NewStreamReduce<S>,
-    M: NewMiddleware<R::ReducedService>,
+    L: Layer<R::ReducedService> + Clone,
+    L::Service: Service,
 {
     reducer: R,
-    middleware: M,
+    layer: L,
     _marker: PhantomData<S>,
 }
 
-impl<S, R, M> NewStreamReduce<S> for NewStreamReduceMiddlewareChain<S, R, M>
+impl<S, R, L> NewStreamReduce<S> for NewStreamReduceLayerChain<S, R, L>
 where
     S: StreamService,
     R: NewStreamReduce<S>,
-    M: NewMiddleware<R::ReducedService>,
+    L: Layer<R::ReducedService> + Clone,
+    L::Service: Service,
 {
-    type ReducedService = M::WrappedService;
-    type Instance = StreamReduceMiddlewareChain<S, R::Instance, M::Instance>;
+    type ReducedService = L::Service;
+    type Instance = StreamReduceLayerChain<S, R::Instance, L>;
 
     fn new_reducer(&self) -> io::Result<Self::Instance> {
-        Ok(self.reducer.new_reducer()?.chain(self.middleware.new_middleware()?))
+        Ok(StreamReduceLayerChain {
+            reducer: self.reducer.new_reducer()?,
+            layer: self.layer.clone(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// A `StreamReduce` that applies a `Layer` to the service produced by an
+/// inner reducer. Produced as the `Instance` of `NewStreamReduceLayerChain`.
+pub struct StreamReduceLayerChain<S, R, L>
+where
+    S: StreamService,
+    R: StreamReduce<S>,
+    L: Layer<R::ReducedService>,
+    L::Service: Service,
+{
+    reducer: R,
+    layer: L,
+    _marker: PhantomData<S>,
+}
+
+impl<S, R, L> StreamReduce<S> for StreamReduceLayerChain<S, R, L>
+where
+    S: StreamService,
+    R: StreamReduce<S>,
+    L: Layer<R::ReducedService>,
+    L::Service: Service,
+{
+    type ReducedService = L::Service;
+
+    fn reduce(self, service: S) -> Self::ReducedService {
+        self.layer.layer(service.reduce(self.reducer))
     }
 }
@@ -0,0 +1,184 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::Duration;
+
+use futures::{Async, Future, Poll};
+use tokio_timer::{Sleep, Timer};
+
+use {Middleware, Service};
+
+/// Error produced when a `Timeout`-wrapped service did not complete
+/// within the configured duration.
+#[derive(Debug)]
+pub struct TimedOut(());
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "request timed out")
+    }
+}
+
+impl StdError for TimedOut {
+    fn description(&self) -> &str {
+        "request timed out"
+    }
+}
+
+/// Middleware that fails a request with `TimedOut` if the wrapped
+/// service does not respond within `delay`.
+///
+/// The wrapped service's `Error` type must implement `From<TimedOut>` so
+/// the timeout can be surfaced through the normal error path.
+pub struct Timeout {
+    delay: Duration,
+    timer: Timer,
+}
+
+impl Timeout {
+    /// Create a new `Timeout` middleware that fails requests taking
+    /// longer than `delay`, using `timer` to schedule the deadline.
+    pub fn new(delay: Duration, timer: Timer) -> Self {
+        Timeout { delay: delay, timer: timer }
+    }
+}
+
+impl<S> Middleware<S> for Timeout
+    where S: Service,
+          S::Error: From<TimedOut>,
+{
+    type WrappedService = TimeoutService<S>;
+
+    fn wrap(self, service: S) -> Self::WrappedService {
+        TimeoutService {
+            service: service,
+            delay: self.delay,
+            timer: self.timer,
+        }
+    }
+}
+
+/// A `Service` that fails requests which take longer than a configured
+/// duration. Produced by `Timeout::wrap`.
+pub struct TimeoutService<S> {
+    service: S,
+    delay: Duration,
+    timer: Timer,
+}
+
+impl<S> Service for TimeoutService<S>
+    where S: Service,
+          S::Error: From<TimedOut>,
+{
+    type Request = S::Request;
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TimeoutFuture<S>;
+
+    fn poll_ready(&self) -> Poll<(), Self::Error> {
+        self.service.poll_ready()
+    }
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        TimeoutFuture {
+            call: self.service.call(req),
+            sleep: self.timer.sleep(self.delay),
+        }
+    }
+}
+
+/// The future returned by `TimeoutService::call`: the inner service's
+/// call future, selected against a timer sleep.
+pub struct TimeoutFuture<S: Service> {
+    call: S::Future,
+    sleep: Sleep,
+}
+
+impl<S> Future for TimeoutFuture<S>
+    where S: Service,
+          S::Error: From<TimedOut>,
+{
+    type Item = S::Response;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        if let Async::Ready(res) = self.call.poll()? {
+            return Ok(Async::Ready(res));
+        }
+
+        match self.sleep.poll() {
+            Ok(Async::Ready(())) => Err(S::Error::from(TimedOut(()))),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            // A timer failure (e.g. the timer was dropped) is not a
+            // request timeout; fall back to waiting on the call alone.
+            Err(_) => Ok(Async::NotReady),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::{Async, Future, Poll, future};
+    use tokio_timer::Timer;
+
+    use Service;
+    use super::{TimedOut, Timeout};
+
+    #[derive(Debug, PartialEq)]
+    struct Error;
+
+    impl From<TimedOut> for Error {
+        fn from(_: TimedOut) -> Error {
+            Error
+        }
+    }
+
+    struct Immediate;
+
+    impl Service for Immediate {
+        type Request = ();
+        type Response = ();
+        type Error = Error;
+        type Future = future::FutureResult<(), Error>;
+
+        fn poll_ready(&self) -> Poll<(), Error> {
+            Ok(Async::Ready(()))
+        }
+
+        fn call(&self, _req: ()) -> Self::Future {
+            future::ok(())
+        }
+    }
+
+    struct Never;
+
+    impl Service for Never {
+        type Request = ();
+        type Response = ();
+        type Error = Error;
+        type Future = future::Empty<(), Error>;
+
+        fn poll_ready(&self) -> Poll<(), Error> {
+            Ok(Async::Ready(()))
+        }
+
+        fn call(&self, _req: ()) -> Self::Future {
+            future::empty()
+        }
+    }
+
+    #[test]
+    fn lets_a_fast_response_through() {
+        let svc = Immediate.wrap(Timeout::new(Duration::from_secs(60), Timer::default()));
+
+        assert_eq!(svc.call(()).wait(), Ok(()));
+    }
+
+    #[test]
+    fn fails_with_timed_out_when_the_deadline_passes() {
+        let svc = Never.wrap(Timeout::new(Duration::from_millis(1), Timer::default()));
+
+        assert_eq!(svc.call(()).wait(), Err(Error));
+    }
+}